@@ -0,0 +1,158 @@
+//! The radix digest abstraction that drives the tree descent.
+//!
+//! Every level of the 256-radix tree consumes one 8-bit index.  Historically
+//! these indices came solely from the internal [`Sponge`], but the descent only
+//! ever needs a *stream of bytes* for a key, so [`NestedMap`] is generic over a
+//! [`BuildRadixDigest`] instead.  The default, [`DefaultSponge`], reproduces the
+//! original behaviour; [`HashDigester`] adapts any [`BuildHasher`] (for a faster
+//! or DoS-resistant hash) into the same byte stream.
+//!
+//! [`NestedMap`]: crate::nested_map::NestedMap
+
+use std::hash::{BuildHasher, Hash, Hasher};
+
+use crate::sponge::Sponge;
+
+/// A per-key stream of 8-bit radix indices, squeezed on demand.
+///
+/// Deep collisions simply keep squeezing, so a digest must be able to yield
+/// bytes indefinitely.
+pub trait RadixDigest {
+    /// Yield the next 8-bit radix index.
+    fn squeeze(&mut self) -> u8;
+
+    /// Fast-forward this digest so it sits at the same depth as `other`.
+    ///
+    /// Used when a collision splits a leaf into a branch: the incumbent key's
+    /// digest must be replayed to the depth already reached by the newcomer.
+    fn matching(&mut self, other: &Self);
+
+    /// A fresh digest for another key, using the same configuration.
+    fn sibling<K: Hash>(&self, key: &K) -> Self;
+}
+
+/// Builds a [`RadixDigest`] for a given key, analogous to [`BuildHasher`].
+pub trait BuildRadixDigest {
+    /// The digest this builder produces.
+    type Digest: RadixDigest;
+
+    /// Start a digest for `key`.
+    fn digest<K: Hash>(&self, key: &K) -> Self::Digest;
+}
+
+/// The default digest: the crate's internal table+XOR [`Sponge`].
+#[derive(Clone, Copy, Default)]
+pub struct DefaultSponge;
+
+impl BuildRadixDigest for DefaultSponge {
+    type Digest = Sponge;
+
+    fn digest<K: Hash>(&self, key: &K) -> Sponge {
+        Sponge::new(key)
+    }
+}
+
+impl RadixDigest for Sponge {
+    fn squeeze(&mut self) -> u8 {
+        Sponge::squeeze(self)
+    }
+
+    fn matching(&mut self, other: &Self) {
+        Sponge::matching(self, other);
+    }
+
+    fn sibling<K: Hash>(&self, key: &K) -> Self {
+        Sponge::new(key)
+    }
+}
+
+/// Adapts a [`BuildHasher`] into a [`BuildRadixDigest`].
+///
+/// The key is hashed twice, once plainly and once salted.  The plain 64-bit
+/// value is fed out one byte at a time, and once its eight bytes are exhausted
+/// the block is re-hashed together with a depth counter and the salted hash to
+/// produce the next eight.
+#[derive(Clone, Default)]
+pub struct HashDigester<S> {
+    build_hasher: S,
+}
+
+impl<S> HashDigester<S> {
+    /// Wrap an existing [`BuildHasher`].
+    pub fn new(build_hasher: S) -> Self {
+        HashDigester { build_hasher }
+    }
+}
+
+impl<S: BuildHasher + Clone> BuildRadixDigest for HashDigester<S> {
+    type Digest = HashDigest<S>;
+
+    fn digest<K: Hash>(&self, key: &K) -> HashDigest<S> {
+        HashDigest::new(self.build_hasher.clone(), key)
+    }
+}
+
+/// A [`RadixDigest`] backed by a [`BuildHasher`].
+pub struct HashDigest<S> {
+    build_hasher: S,
+    /// The current eight-byte block.
+    block: u64,
+    /// A second, independently salted hash of the key, folded into every
+    /// re-hash so that two keys sharing a 64-bit `block` still diverge.
+    seed: u64,
+    /// Total bytes squeezed so far; `count % 8` indexes into `block`.
+    count: usize,
+}
+
+impl<S: BuildHasher + Clone> HashDigest<S> {
+    fn new<K: Hash>(build_hasher: S, key: &K) -> Self {
+        let mut hasher = build_hasher.build_hasher();
+        key.hash(&mut hasher);
+
+        // Derive a second, salted hash of the key.  Re-seeding each re-hash
+        // with it keeps key-derived material in the stream: without it two
+        // distinct keys that collide in the 64-bit `block` would share an
+        // identical infinite squeeze stream, making `with_two_entries` recurse
+        // forever (the `DefaultSponge`'s length padding avoids this by
+        // construction).
+        let mut salt = build_hasher.build_hasher();
+        0x9E37_79B9_7F4A_7C15u64.hash(&mut salt);
+        key.hash(&mut salt);
+
+        HashDigest {
+            build_hasher,
+            block: hasher.finish(),
+            seed: salt.finish(),
+            count: 0,
+        }
+    }
+}
+
+impl<S: BuildHasher + Clone> RadixDigest for HashDigest<S> {
+    fn squeeze(&mut self) -> u8 {
+        let offset = self.count % 8;
+        // Every eight bytes, re-hash the spent block with the depth counter to
+        // keep the stream going for deep collisions.
+        if self.count != 0 && offset == 0 {
+            let mut hasher = self.build_hasher.build_hasher();
+            (self.count as u64).hash(&mut hasher);
+            self.seed.hash(&mut hasher);
+            self.block.hash(&mut hasher);
+            self.block = hasher.finish();
+        }
+
+        let byte = (self.block >> (offset * 8)) as u8;
+        self.count += 1;
+        byte
+    }
+
+    fn matching(&mut self, other: &Self) {
+        while self.count < other.count {
+            self.squeeze();
+        }
+    }
+
+    fn sibling<K: Hash>(&self, key: &K) -> Self {
+        HashDigest::new(self.build_hasher.clone(), key)
+    }
+}
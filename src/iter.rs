@@ -0,0 +1,106 @@
+//! Guard-scoped iterators over the radix tree.
+//!
+//! Because the map is lock-free, iteration is only sound while a [`Guard`] is
+//! pinned: the yielded references borrow through the guard's lifetime, so no
+//! reclamation can happen mid-walk.  Each iterator keeps an explicit traversal
+//! stack of `(&Table, usize)` frames — the current table together with the
+//! next bucket index to visit — instead of recursing.
+
+use std::hash::Hash;
+
+use crossbeam_epoch::Guard;
+
+use crate::table::{Bucket, Entry, Table};
+
+/// An iterator over the live key-value pairs of the map.
+///
+/// Yields `(&K, &V)` for every `Bucket::Leaf` whose `value` is `Some`, skipping
+/// empty slots and tombstoned (`None`-valued) leaves.
+pub struct Iter<'g, K: Hash + Eq, V> {
+    guard: &'g Guard,
+    /// Traversal stack of `(table, next bucket index)` frames.
+    stack: Vec<(&'g Table<K, V>, usize)>,
+}
+
+impl<'g, K: Hash + Eq, V> Iter<'g, K, V> {
+    /// Start a walk at `root`, scoped to `guard`.
+    pub(crate) fn new(root: &'g Table<K, V>, guard: &'g Guard) -> Self {
+        Iter {
+            guard,
+            stack: vec![(root, 0)],
+        }
+    }
+}
+
+impl<'g, K: Hash + Eq, V> Iterator for Iter<'g, K, V> {
+    type Item = (&'g K, &'g V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((table, index)) = self.stack.pop() {
+            if index >= Table::<K, V>::BUCKETS {
+                continue;
+            }
+
+            let bucket = table.load(index, self.guard);
+            match unsafe { bucket.as_ref() } {
+                None => {
+                    self.stack.push((table, index + 1));
+                }
+                Some(Bucket::Leaf(Entry { key, value })) => {
+                    self.stack.push((table, index + 1));
+                    if let Some(value) = value {
+                        return Some((key, value));
+                    }
+                }
+                Some(Bucket::Branch(child)) => {
+                    self.stack.push((table, index + 1));
+                    self.stack.push((child, 0));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// An iterator over the live keys of the map.
+pub struct Keys<'g, K: Hash + Eq, V> {
+    inner: Iter<'g, K, V>,
+}
+
+impl<'g, K: Hash + Eq, V> Keys<'g, K, V> {
+    pub(crate) fn new(root: &'g Table<K, V>, guard: &'g Guard) -> Self {
+        Keys {
+            inner: Iter::new(root, guard),
+        }
+    }
+}
+
+impl<'g, K: Hash + Eq, V> Iterator for Keys<'g, K, V> {
+    type Item = &'g K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+}
+
+/// An iterator over the live values of the map.
+pub struct Values<'g, K: Hash + Eq, V> {
+    inner: Iter<'g, K, V>,
+}
+
+impl<'g, K: Hash + Eq, V> Values<'g, K, V> {
+    pub(crate) fn new(root: &'g Table<K, V>, guard: &'g Guard) -> Self {
+        Values {
+            inner: Iter::new(root, guard),
+        }
+    }
+}
+
+impl<'g, K: Hash + Eq, V> Iterator for Values<'g, K, V> {
+    type Item = &'g V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+}
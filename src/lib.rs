@@ -0,0 +1,8 @@
+pub mod digest;
+pub mod iter;
+pub mod nested_map;
+pub mod par;
+#[cfg(feature = "serde")]
+mod serde_impls;
+pub mod sponge;
+pub mod table;
@@ -14,6 +14,8 @@
 //!
 //! The permutation is a simple table+XOR based length-padded function, which
 //! is applied to avoid excessive depth (this is what makes it a "hash table").
+//! The permutation is pluggable through the [`BuildRadixDigest`] type parameter;
+//! the default [`DefaultSponge`] reproduces the original behaviour.
 //!
 //! See [this blog post](https://ticki.github.io/blog/an-atomic-hash-table/)
 //! for details.
@@ -21,32 +23,51 @@
 use crossbeam_epoch::{Guard, Owned};
 use std::fmt::Display;
 use std::hash::Hash;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use crate::sponge::Sponge;
+use crate::digest::{BuildRadixDigest, DefaultSponge};
+use crate::iter::{Iter, Keys, Values};
+use crate::par::Par;
 use crate::table::{Bucket, Entry, Table};
 
 /// A lock-free, concurrent hash map.
-pub struct NestedMap<K: Hash + Eq + Display, V> {
+///
+/// The `S` type parameter selects the [`BuildRadixDigest`] driving the radix
+/// descent; it defaults to [`DefaultSponge`], the internal table+XOR sponge.
+pub struct NestedMap<K: Hash + Eq + Display, V, S = DefaultSponge> {
     /// The root table of the hash map.
     root: Table<K, V>,
+    /// The digest builder producing the radix indices for each key.
+    hasher: S,
+    /// The number of live entries, tracked as slots become (un)occupied.
+    len: AtomicUsize,
 }
 
-impl<'a, K: 'a + Hash + Eq + Display, V: 'a> Default for NestedMap<K, V> {
+impl<'a, K: 'a + Hash + Eq + Display, V: 'a> Default for NestedMap<K, V, DefaultSponge> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<'a, K: 'a + Hash + Eq + Display, V> NestedMap<K, V> {
+impl<'a, K: 'a + Hash + Eq + Display, V> NestedMap<K, V, DefaultSponge> {
     pub fn new() -> Self {
+        Self::with_hasher(DefaultSponge)
+    }
+}
+
+impl<'a, K: 'a + Hash + Eq + Display, V, S: BuildRadixDigest> NestedMap<K, V, S> {
+    /// Create an empty map driven by the given digest builder.
+    pub fn with_hasher(hasher: S) -> Self {
         Self {
             root: Table::default(),
+            hasher,
+            len: AtomicUsize::new(0),
         }
     }
 
     /// Lookups a key.
     pub fn lookup(&'a self, key: &K, guard: &'a Guard) -> Option<&V> {
-        self.root.lookup(key, Sponge::new(&key), guard)
+        self.root.lookup(key, self.hasher.digest(key), guard)
     }
 
     /// Insert a key with a certain value into the map.
@@ -54,14 +75,15 @@ impl<'a, K: 'a + Hash + Eq + Display, V> NestedMap<K, V> {
     /// - Returns `Some(value)` for the given `value` if `key` is already occupied.
     /// - Returns `None` if key was unoccupied.
     pub fn insert(&self, key: K, val: V, guard: &Guard) -> Option<V> {
-        let mut sponge = Sponge::new(&key);
+        let mut digest = self.hasher.digest(&key);
         self.root.insert(
             Owned::new(Bucket::Leaf(Entry {
                 key,
                 value: Some(val),
             }))
             .into_shared(guard),
-            &mut sponge,
+            &mut digest,
+            &self.len,
             guard,
         )
     }
@@ -70,6 +92,69 @@ impl<'a, K: 'a + Hash + Eq + Display, V> NestedMap<K, V> {
     ///
     /// If any, the removed value is returned.
     pub fn delete(&self, key: &K, guard: &Guard) -> Result<V, ()> {
-        self.root.delete(key, &mut Sponge::new(&key), guard)
+        self.root.delete(key, &mut self.hasher.digest(key), &self.len, guard)
+    }
+
+    /// The number of live entries in the map.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Whether the map holds no live entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Atomically replace the value under `key` with `f(&current)`.
+    ///
+    /// Returns the previous value, or `None` if `key` is absent.  Unlike
+    /// [`insert`](Self::insert), concurrent updates are not lost: the closure is
+    /// re-applied on a lost CAS race.
+    pub fn update<F: Fn(&V) -> V>(&self, key: K, f: F, guard: &Guard) -> Option<V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut digest = self.hasher.digest(&key);
+        self.root.update(key, f, &mut digest, guard)
+    }
+
+    /// Atomically insert, update, or remove the value under `key`.
+    ///
+    /// The current value (or `None` when absent) is passed to `f`; `Some`
+    /// upserts the returned value and `None` removes the key.
+    pub fn alter<F: Fn(Option<V>) -> Option<V>>(&self, key: K, f: F, guard: &Guard)
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let mut digest = self.hasher.digest(&key);
+        self.root.alter(key, f, &mut digest, &self.len, guard)
+    }
+
+    /// An iterator over all live key-value pairs, borrowing through `guard`.
+    ///
+    /// The walk is only sound while `guard` stays pinned, so the yielded
+    /// references share its lifetime.
+    pub fn iter(&'a self, guard: &'a Guard) -> Iter<'a, K, V> {
+        Iter::new(&self.root, guard)
+    }
+
+    /// An iterator over all live keys, borrowing through `guard`.
+    pub fn keys(&'a self, guard: &'a Guard) -> Keys<'a, K, V> {
+        Keys::new(&self.root, guard)
+    }
+
+    /// An iterator over all live values, borrowing through `guard`.
+    pub fn values(&'a self, guard: &'a Guard) -> Values<'a, K, V> {
+        Values::new(&self.root, guard)
+    }
+
+    /// A [`rayon`] parallel iterator over all live key-value pairs.
+    ///
+    /// As with [`iter`](Self::iter), `guard` must stay pinned for the whole
+    /// parallel walk; the yielded references borrow through it.
+    pub fn par_iter(&'a self, guard: &'a Guard) -> Par<'a, K, V> {
+        Par::new(&self.root, guard)
     }
 }
@@ -0,0 +1,166 @@
+//! A [`rayon`] parallel read view over the radix tree.
+//!
+//! Splitting maps naturally onto a 256-radix tree: a producer owns a `&Table`
+//! together with a `[lo, hi)` sub-range of its bucket indices.  [`split`] halves
+//! that range; once the range narrows to a single `Branch`, the child table is
+//! re-entered as a fresh full-range producer.  Splits are therefore cheap and
+//! bounded by the depth of the tree, which gives good work-stealing without any
+//! locks.
+//!
+//! Like the sequential [`Iter`](crate::iter::Iter), the view borrows through a
+//! pinned [`Guard`]; the caller **must** keep that guard pinned for the whole
+//! parallel walk, otherwise yielded references could dangle.
+//!
+//! A [`Guard`] is neither `Send` nor `Sync`, so it cannot be handed to the
+//! work-stealing workers directly.  Instead each worker pins its own guard for
+//! the loads it performs; that is sound because the caller's guard (whose
+//! lifetime is `'g`) keeps the global epoch from advancing for the duration of
+//! the walk, so no bucket observed by any worker can be reclaimed.
+//!
+//! [`split`]: rayon::iter::plumbing::UnindexedProducer::split
+
+use std::hash::Hash;
+use std::mem;
+
+use crossbeam_epoch::{pin, Guard, Shared};
+use rayon::iter::plumbing::{bridge_unindexed, Folder, UnindexedConsumer, UnindexedProducer};
+use rayon::iter::ParallelIterator;
+
+use crate::table::{Bucket, Entry, Table};
+
+/// A parallel iterator over the live key-value pairs of the map.
+///
+/// Created by [`NestedMap::par_iter`](crate::nested_map::NestedMap::par_iter).
+pub struct Par<'g, K: Hash + Eq, V> {
+    root: &'g Table<K, V>,
+}
+
+impl<'g, K: Hash + Eq, V> Par<'g, K, V> {
+    pub(crate) fn new(root: &'g Table<K, V>, _guard: &'g Guard) -> Self {
+        // The guard is not stored (a `Guard` is `!Send`, which would make `Par`
+        // non-`Send` and unusable as a `ParallelIterator`).  Its `'g` lifetime
+        // still bounds `root`, so the caller's pin outlives the whole walk.
+        Par { root }
+    }
+}
+
+impl<'g, K: Hash + Eq + Send + Sync, V: Send + Sync> ParallelIterator for Par<'g, K, V> {
+    type Item = (&'g K, &'g V);
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        bridge_unindexed(
+            RadixProducer {
+                table: self.root,
+                lo: 0,
+                hi: Table::<K, V>::BUCKETS,
+            },
+            consumer,
+        )
+    }
+}
+
+/// Load bucket `index` of `table`, extending the borrow to `'g`.
+///
+/// # Safety
+///
+/// The caller of [`NestedMap::par_iter`](crate::nested_map::NestedMap::par_iter)
+/// holds a guard pinned for the whole walk (its lifetime is `'g`), so the global
+/// epoch cannot advance to reclaim any bucket we observe.  The per-worker pin
+/// taken here only participates in the epoch; the borrow it yields is valid for
+/// all of `'g`.
+unsafe fn load<'g, K: Hash + Eq, V>(
+    table: &'g Table<K, V>,
+    index: usize,
+) -> Shared<'g, Bucket<K, V>> {
+    let guard = pin();
+    mem::transmute::<Shared<'_, Bucket<K, V>>, Shared<'g, Bucket<K, V>>>(table.load(index, &guard))
+}
+
+/// A producer owning one table and a `[lo, hi)` slice of its bucket indices.
+struct RadixProducer<'g, K: Hash + Eq, V> {
+    table: &'g Table<K, V>,
+    lo: usize,
+    hi: usize,
+}
+
+impl<'g, K: Hash + Eq + Send + Sync, V: Send + Sync> UnindexedProducer
+    for RadixProducer<'g, K, V>
+{
+    type Item = (&'g K, &'g V);
+
+    fn split(self) -> (Self, Option<Self>) {
+        let len = self.hi - self.lo;
+
+        if len <= 1 {
+            // A single bucket that is a branch re-enters the child table as a
+            // fresh full-range producer; the current frame is left empty so the
+            // branch is not also walked by `fold_with`.
+            if len == 1 {
+                let bucket = unsafe { load(self.table, self.lo) };
+                if let Some(Bucket::Branch(child)) = unsafe { bucket.as_ref() } {
+                    let left = RadixProducer {
+                        table: self.table,
+                        lo: self.lo,
+                        hi: self.lo,
+                    };
+                    let right = RadixProducer {
+                        table: child,
+                        lo: 0,
+                        hi: Table::<K, V>::BUCKETS,
+                    };
+                    return (left, Some(right));
+                }
+            }
+            return (self, None);
+        }
+
+        let mid = self.lo + len / 2;
+        let right = RadixProducer {
+            table: self.table,
+            lo: mid,
+            hi: self.hi,
+        };
+        let left = RadixProducer {
+            table: self.table,
+            lo: self.lo,
+            hi: mid,
+        };
+        (left, Some(right))
+    }
+
+    fn fold_with<F>(self, mut folder: F) -> F
+    where
+        F: Folder<Self::Item>,
+    {
+        for index in self.lo..self.hi {
+            let bucket = unsafe { load(self.table, index) };
+            match unsafe { bucket.as_ref() } {
+                Some(Bucket::Leaf(Entry {
+                    key,
+                    value: Some(value),
+                })) => {
+                    folder = folder.consume((key, value));
+                }
+                Some(Bucket::Branch(child)) => {
+                    // Walk the sub-table sequentially within this fold.
+                    folder = RadixProducer {
+                        table: child,
+                        lo: 0,
+                        hi: Table::<K, V>::BUCKETS,
+                    }
+                    .fold_with(folder);
+                }
+                _ => {}
+            }
+
+            if folder.full() {
+                break;
+            }
+        }
+
+        folder
+    }
+}
@@ -0,0 +1,86 @@
+//! Optional [`serde`] support, enabled by the `serde` feature.
+//!
+//! [`Serialize`] walks the tree as a map and emits only the live `Leaf`
+//! entries (those whose value is `Some`); [`Deserialize`] builds a fresh map by
+//! `insert`-ing each decoded pair under a temporary pinned guard.  This lets
+//! callers persist and restore the map to JSON, bincode, etc. without first
+//! draining it into a [`std::collections::HashMap`].
+
+use std::fmt;
+use std::hash::Hash;
+use std::fmt::Display;
+use std::marker::PhantomData;
+
+use crossbeam_epoch::pin;
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::nested_map::NestedMap;
+
+impl<K, V> Serialize for NestedMap<K, V>
+where
+    K: Hash + Eq + Display + Serialize,
+    V: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let guard = pin();
+        // A known length is required by length-prefixed formats such as
+        // bincode; `len()` tracks exactly the live entries `iter` will yield.
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter(&guard) {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de, K, V> Deserialize<'de> for NestedMap<K, V>
+where
+    K: Hash + Eq + Display + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(NestedMapVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Builds a [`NestedMap`] from a serialized map, inserting each pair as it is
+/// decoded.
+struct NestedMapVisitor<K, V> {
+    // `fn() -> (K, V)` carries the right variance without re-imposing
+    // `NestedMap`'s `K: Hash + Eq + Display` bounds on the bare struct.
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'de, K, V> Visitor<'de> for NestedMapVisitor<K, V>
+where
+    K: Hash + Eq + Display + Deserialize<'de>,
+    V: Deserialize<'de>,
+{
+    type Value = NestedMap<K, V>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a map")
+    }
+
+    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let map = NestedMap::new();
+        let guard = pin();
+        while let Some((key, value)) = access.next_entry()? {
+            map.insert(key, value, &guard);
+        }
+        Ok(map)
+    }
+}
@@ -1,11 +1,11 @@
 use std::hash::Hash;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use core::ptr;
 
 use arr_macro::arr;
 use crossbeam_epoch::{Atomic, Guard, Owned, Shared};
 
-use crate::sponge::Sponge;
+use crate::digest::RadixDigest;
 
 pub struct Entry<K: Hash + Eq, V> {
     pub key: K,
@@ -45,35 +45,38 @@ impl<K: Hash + Eq, V> Bucket<K, V> {
 
 
 impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
+    /// The number of buckets (radix) in every table.
+    pub(crate) const BUCKETS: usize = 256;
+
     /// Create a table containing two particular entries.
-    fn with_two_entries(
-        entry1: Shared<'a, Bucket<K, V>>, sponge1: &mut Sponge,
-        entry2: Shared<'a, Bucket<K, V>>, sponge2: &mut Sponge,
+    fn with_two_entries<D: RadixDigest>(
+        entry1: Shared<'a, Bucket<K, V>>, digest1: &mut D,
+        entry2: Shared<'a, Bucket<K, V>>, digest2: &mut D,
     ) -> Self {
         let mut table = Table::default();
 
-        // Squeeze the two sponges.
-        let idx1 = sponge1.squeeze() as usize;
-        let idx2 = sponge2.squeeze() as usize;
+        // Squeeze the two digests.
+        let idx1 = digest1.squeeze() as usize;
+        let idx2 = digest2.squeeze() as usize;
 
         if idx1 != idx2 {
             // If it doesn't collide, insert the two entries
             table.buckets[idx1].store(entry1, Ordering::Relaxed);
             table.buckets[idx2].store(entry2, Ordering::Relaxed);
         } else {
-            // The two positions from the sponge matched, so we must place another branch.
+            // The two positions from the digest matched, so we must place another branch.
             table.buckets[idx1 as usize] = Atomic::new(Bucket::Branch(
-                Table::with_two_entries(entry1, sponge1, entry2, sponge2)
+                Table::with_two_entries(entry1, digest1, entry2, digest2)
             ));
         }
 
         table
     }
 
-    /// Get the value associated with some key, given its sponge.
-    pub fn lookup(&'a self, key: &K, mut sponge: Sponge, guard: &'a Guard) -> Option<&'a V>
+    /// Get the value associated with some key, given its digest.
+    pub fn lookup<D: RadixDigest>(&'a self, key: &K, mut digest: D, guard: &'a Guard) -> Option<&'a V>
     {
-        let bucket = self.buckets[sponge.squeeze() as usize].load(Ordering::Relaxed, guard);
+        let bucket = self.buckets[digest.squeeze() as usize].load(Ordering::Relaxed, guard);
 
         match unsafe{ bucket.as_ref() }  {
             None => { None },
@@ -89,19 +92,19 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
             Some(Bucket::Branch(table)) => {
                 // The bucket is a branch with another table, so we recurse and look up in said
                 // sub-table.
-                table.lookup(key, sponge, guard)
+                table.lookup(key, digest, guard)
             }
         }
     }
 
-    /// Insert a key-value pair into the table, given its sponge.
+    /// Insert a key-value pair into the table, given its digest.
     ///
     /// - Returns `Some(value)` for the given `value` if `key` is already occupied.
     /// - Returns `None` if key was unoccupied.
-    pub fn insert(&'a self, entry: Shared<Bucket<K, V>>, sponge: &mut Sponge, guard: &Guard) -> Option<V> {
-        let index = sponge.squeeze() as usize;
+    pub fn insert<D: RadixDigest>(&'a self, entry: Shared<Bucket<K, V>>, digest: &mut D, len: &AtomicUsize, guard: &Guard) -> Option<V> {
+        let index = digest.squeeze() as usize;
         loop {
-            // We squeeze the sponge to get the right bucket of our table
+            // We squeeze the digest to get the right bucket of our table
             let bucket = self.buckets[index].load(Ordering::Relaxed, guard);
 
             match unsafe{ bucket.as_ref() } {
@@ -113,14 +116,18 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
                         Ordering::Relaxed,
                         guard
                     ) {
-                        Ok(_) => { return None; },
+                        Ok(_) => {
+                            // A previously unoccupied slot is now occupied.
+                            len.fetch_add(1, Ordering::Relaxed);
+                            return None;
+                        },
                         Err(_) => { continue; }
                     };
                 }
                 Some(bucket_) => {
                     match bucket_ {
                         Bucket::Branch(table) => {
-                            return table.insert(entry, sponge, guard);
+                            return table.insert(entry, digest, len, guard);
                         },
                         Bucket::Leaf(entry2) =>  {
                             if unsafe{ entry.deref() }.get_key().unwrap() == &entry2.key {
@@ -130,32 +137,47 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
                                     Ordering::Relaxed,
                                     guard
                                 ){
-                                    Ok(_) => { 
+                                    Ok(_) => {
                                         let old_entry = unsafe{ ptr::read(&*bucket.as_raw()) };
                                         match old_entry.into_value() {
                                             Ok(v) => { return Some(v); },
-                                            Err(_) => { return None; }
+                                            Err(_) => {
+                                                // Replaced a tombstoned (`None`) leaf: a
+                                                // fresh occupancy.
+                                                len.fetch_add(1, Ordering::Relaxed);
+                                                return None;
+                                            }
                                         }
                                     },
                                     Err(_) => { continue; }
                                 }
                             }else{
-                                let mut sponge2 = Sponge::new(&entry2.key);
-                                sponge2.matching(&sponge);
+                                // Split off *copies* of the digest so the live
+                                // `digest` is not advanced: on a lost CAS the
+                                // retry (which may descend into the new branch)
+                                // must still squeeze from this exact level.
+                                let mut digest1 = digest.sibling(unsafe { entry.deref() }.get_key().unwrap());
+                                digest1.matching(digest);
+                                let mut digest2 = digest.sibling(&entry2.key);
+                                digest2.matching(digest);
                                 match self.buckets[index].compare_and_set(
                                     bucket,
                                     Owned::new(
                                         Bucket::Branch(
                                             Table::with_two_entries(
-                                                entry, sponge,
-                                                bucket, &mut sponge2
+                                                entry, &mut digest1,
+                                                bucket, &mut digest2
                                                 )
                                             )
                                         ),
                                     Ordering::Relaxed,
                                     guard
                                 ){
-                                    Ok(_) => { return None; },
+                                    Ok(_) => {
+                                        // The new key was added alongside the incumbent.
+                                        len.fetch_add(1, Ordering::Relaxed);
+                                        return None;
+                                    },
                                     Err(_) => { continue; }
                                 }
                             }
@@ -166,16 +188,24 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
         }
     }
 
-    pub fn delete(&self, key: &K, sponge: &mut Sponge, guard: &Guard) -> Result<V, ()> {
-        let index = sponge.squeeze() as usize;
+    /// Load the bucket at `index`, scoped to `guard`.
+    ///
+    /// Used by the guard-scoped iterators to walk the radix tree without
+    /// taking ownership of any slot.
+    pub(crate) fn load(&'a self, index: usize, guard: &'a Guard) -> Shared<'a, Bucket<K, V>> {
+        self.buckets[index].load(Ordering::Relaxed, guard)
+    }
+
+    pub fn delete<D: RadixDigest>(&self, key: &K, digest: &mut D, len: &AtomicUsize, guard: &Guard) -> Result<V, ()> {
+        let index = digest.squeeze() as usize;
         loop {
             let bucket = self.buckets[index].load(Ordering::Relaxed, guard);
             match unsafe{ bucket.as_ref() } {
                 None => { return Err(()); },
-                Some(bucket_) => { 
+                Some(bucket_) => {
                     match bucket_ {
                         Bucket::Branch(table) => {
-                            return table.delete(key, sponge, guard);
+                            return table.delete(key, digest, len, guard);
                         },
                         Bucket::Leaf(_) => {
                             match self.buckets[index].compare_and_set(
@@ -186,7 +216,12 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
                                 ){
                                 Ok(_) => {
                                     let old_entry = unsafe{ ptr::read(&*bucket.as_raw()) };
-                                    return old_entry.into_value();
+                                    let removed = old_entry.into_value();
+                                    if removed.is_ok() {
+                                        // Only a live value counted towards the length.
+                                        len.fetch_sub(1, Ordering::Relaxed);
+                                    }
+                                    return removed;
                                 },
                                 Err(_) => { continue; }
                             }
@@ -196,6 +231,203 @@ impl<'a, K:'a + Hash + Eq, V: 'a> Table<K, V> {
             }
         }
     }
+
+    /// Atomically read-modify-write the value under `key`.
+    ///
+    /// Descends to the leaf bucket the same way [`lookup`](Self::lookup) does,
+    /// then runs a CAS loop: the current value is fed to `f` and the resulting
+    /// value is swapped in, retrying on a lost race.  Returns the previous
+    /// value, or `None` if `key` is absent (including a tombstoned leaf).
+    pub fn update<F: Fn(&V) -> V, D: RadixDigest>(
+        &'a self,
+        key: K,
+        f: F,
+        digest: &mut D,
+        guard: &Guard,
+    ) -> Option<V>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        let index = digest.squeeze() as usize;
+        loop {
+            let bucket = self.buckets[index].load(Ordering::Relaxed, guard);
+            match unsafe { bucket.as_ref() } {
+                None => return None,
+                Some(Bucket::Branch(table)) => return table.update(key, f, digest, guard),
+                Some(Bucket::Leaf(Entry { key: k, value })) => {
+                    if k != &key {
+                        return None;
+                    }
+                    let previous = match value {
+                        // The old leaf is still visible to concurrent readers,
+                        // so the returned value is cloned rather than moved out.
+                        Some(v) => v.clone(),
+                        None => return None,
+                    };
+                    let new_value = f(&previous);
+                    match self.buckets[index].compare_and_set(
+                        bucket,
+                        Owned::new(Bucket::Leaf(Entry {
+                            key: key.clone(),
+                            value: Some(new_value),
+                        })),
+                        Ordering::Relaxed,
+                        guard,
+                    ) {
+                        Ok(_) => {
+                            // Hand the displaced leaf to the epoch collector; a
+                            // reader may still be dereferencing it.
+                            unsafe { guard.defer_destroy(bucket) };
+                            return Some(previous);
+                        }
+                        Err(_) => continue,
+                    }
+                }
+            }
+        }
+    }
+
+    /// Atomically insert, update, or remove the value under `key`.
+    ///
+    /// The current value (`None` when the key is absent or tombstoned) is fed to
+    /// `f`; a returned `Some` upserts that value and a returned `None` removes
+    /// the key.  Like [`update`](Self::update) this is a CAS loop that retries on
+    /// a lost race.
+    pub fn alter<F: Fn(Option<V>) -> Option<V>, D: RadixDigest>(
+        &'a self,
+        key: K,
+        f: F,
+        digest: &mut D,
+        len: &AtomicUsize,
+        guard: &Guard,
+    ) where
+        K: Clone,
+        V: Clone,
+    {
+        let index = digest.squeeze() as usize;
+        loop {
+            let bucket = self.buckets[index].load(Ordering::Relaxed, guard);
+            match unsafe { bucket.as_ref() } {
+                None => {
+                    // Empty slot: only a returned `Some` has any effect.
+                    match f(None) {
+                        None => return,
+                        Some(new_value) => {
+                            match self.buckets[index].compare_and_set(
+                                bucket,
+                                Owned::new(Bucket::Leaf(Entry {
+                                    key: key.clone(),
+                                    value: Some(new_value),
+                                })),
+                                Ordering::Relaxed,
+                                guard,
+                            ) {
+                                Ok(_) => {
+                                    len.fetch_add(1, Ordering::Relaxed);
+                                    return;
+                                }
+                                Err(_) => continue,
+                            }
+                        }
+                    }
+                }
+                Some(Bucket::Branch(table)) => return table.alter(key, f, digest, len, guard),
+                Some(Bucket::Leaf(Entry { key: k, value })) if k == &key => {
+                    // The key is present (possibly tombstoned).
+                    let occupied = value.is_some();
+                    match f(value.clone()) {
+                        Some(new_value) => {
+                            match self.buckets[index].compare_and_set(
+                                bucket,
+                                Owned::new(Bucket::Leaf(Entry {
+                                    key: key.clone(),
+                                    value: Some(new_value),
+                                })),
+                                Ordering::Relaxed,
+                                guard,
+                            ) {
+                                Ok(_) => {
+                                    // The displaced leaf may still be read
+                                    // concurrently; defer its reclamation.
+                                    unsafe { guard.defer_destroy(bucket) };
+                                    // A tombstoned leaf becoming live is a fresh occupancy.
+                                    if !occupied {
+                                        len.fetch_add(1, Ordering::Relaxed);
+                                    }
+                                    return;
+                                }
+                                Err(_) => continue,
+                            }
+                        }
+                        None => {
+                            match self.buckets[index].compare_and_set(
+                                bucket,
+                                Shared::null(),
+                                Ordering::Relaxed,
+                                guard,
+                            ) {
+                                Ok(_) => {
+                                    // Reclaim the removed leaf once no reader
+                                    // can still observe it.
+                                    unsafe { guard.defer_destroy(bucket) };
+                                    if occupied {
+                                        len.fetch_sub(1, Ordering::Relaxed);
+                                    }
+                                    return;
+                                }
+                                Err(_) => continue,
+                            }
+                        }
+                    }
+                }
+                Some(Bucket::Leaf(entry2)) => {
+                    // A different key occupies this slot, so `key` is absent; a
+                    // returned `Some` splits the leaf into a branch just as
+                    // `insert` does.
+                    let new_value = match f(None) {
+                        None => return,
+                        Some(new_value) => new_value,
+                    };
+                    // Split off copies of the digest so the live `digest` stays
+                    // at this level: on a lost CAS the retry may descend into
+                    // the freshly installed branch and must squeeze from here.
+                    let mut digest1 = digest.sibling(&key);
+                    digest1.matching(digest);
+                    let mut digest2 = digest.sibling(&entry2.key);
+                    digest2.matching(digest);
+                    let entry = Owned::new(Bucket::Leaf(Entry {
+                        key: key.clone(),
+                        value: Some(new_value),
+                    }))
+                    .into_shared(guard);
+                    match self.buckets[index].compare_and_set(
+                        bucket,
+                        Owned::new(Bucket::Branch(Table::with_two_entries(
+                            entry,
+                            &mut digest1,
+                            bucket,
+                            &mut digest2,
+                        ))),
+                        Ordering::Relaxed,
+                        guard,
+                    ) {
+                        Ok(_) => {
+                            len.fetch_add(1, Ordering::Relaxed);
+                            return;
+                        }
+                        Err(_) => {
+                            // The CAS lost, so our speculative leaf never became
+                            // reachable; reclaim it before retrying instead of
+                            // leaking a fresh allocation on every lost race.
+                            drop(unsafe { entry.into_owned() });
+                            continue;
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
 
 impl<K: Hash + Eq, V> Default for Table<K, V> {
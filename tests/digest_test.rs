@@ -0,0 +1,60 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+use crossbeam_epoch::pin;
+use nested_map::digest::HashDigester;
+use nested_map::nested_map::NestedMap;
+
+#[test]
+fn hash_digester_round_trips_over_random_state() {
+    let map = NestedMap::with_hasher(HashDigester::new(RandomState::new()));
+    for i in 0..10000 {
+        assert!(map.insert(i, i * 7, &pin()).is_none());
+    }
+    for i in 0..10000 {
+        assert_eq!(map.lookup(&i, &pin()), Some(&(i * 7)));
+    }
+    assert_eq!(map.len(), 10000);
+}
+
+/// A deterministic FNV-1a hasher: a full 64-bit output folding every byte, so
+/// distinct keys keep distinct squeeze streams and deep colliding splits always
+/// terminate rather than recursing forever.
+#[derive(Clone, Default)]
+struct Fnv;
+
+impl BuildHasher for Fnv {
+    type Hasher = FnvHasher;
+
+    fn build_hasher(&self) -> FnvHasher {
+        FnvHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+struct FnvHasher(u64);
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn hash_digester_keeps_colliding_keys_distinct() {
+    // Many keys share low-byte radix indices under this hasher, forcing branch
+    // splits at every level; every key must still round-trip.
+    let map = NestedMap::with_hasher(HashDigester::new(Fnv));
+    for i in 0..5000 {
+        assert!(map.insert(i, i, &pin()).is_none());
+    }
+    for i in 0..5000 {
+        assert_eq!(map.lookup(&i, &pin()), Some(&i));
+    }
+    assert_eq!(map.len(), 5000);
+}
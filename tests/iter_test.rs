@@ -0,0 +1,54 @@
+use std::collections::HashSet;
+
+use crossbeam_epoch::pin;
+use nested_map::nested_map::NestedMap;
+
+#[test]
+fn iter_yields_all_live_entries() {
+    let map = NestedMap::default();
+    for i in 0..1000 {
+        assert!(map.insert(i, i * 2, &pin()).is_none());
+    }
+
+    let guard = pin();
+    let mut pairs: Vec<_> = map.iter(&guard).map(|(k, v)| (*k, *v)).collect();
+    pairs.sort();
+
+    let expected: Vec<_> = (0..1000).map(|i| (i, i * 2)).collect();
+    assert_eq!(pairs, expected);
+}
+
+#[test]
+fn iter_skips_deleted_entries() {
+    let map = NestedMap::default();
+    for i in 0..100 {
+        map.insert(i, i, &pin());
+    }
+    for i in (0..100).step_by(2) {
+        assert!(map.delete(&i, &pin()).is_ok());
+    }
+
+    let guard = pin();
+    let mut keys: Vec<_> = map.keys(&guard).copied().collect();
+    keys.sort();
+
+    assert_eq!(keys, (1..100).step_by(2).collect::<Vec<_>>());
+}
+
+#[test]
+fn keys_and_values_agree_with_iter() {
+    let map = NestedMap::default();
+    for i in 0..500 {
+        map.insert(i, i + 7, &pin());
+    }
+
+    let guard = pin();
+    let keys: HashSet<_> = map.keys(&guard).copied().collect();
+    let values: HashSet<_> = map.values(&guard).copied().collect();
+
+    assert_eq!(keys.len(), 500);
+    for (k, v) in map.iter(&guard) {
+        assert!(keys.contains(k));
+        assert!(values.contains(v));
+    }
+}
@@ -0,0 +1,49 @@
+use crossbeam_epoch::pin;
+use nested_map::nested_map::NestedMap;
+
+#[test]
+fn len_tracks_inserts_and_deletes() {
+    let map = NestedMap::default();
+    assert!(map.is_empty());
+
+    for i in 0..1000 {
+        map.insert(i, i, &pin());
+    }
+    assert_eq!(map.len(), 1000);
+    assert!(!map.is_empty());
+
+    for i in 0..500 {
+        assert!(map.delete(&i, &pin()).is_ok());
+    }
+    assert_eq!(map.len(), 500);
+}
+
+#[test]
+fn replacing_a_value_does_not_change_len() {
+    let map = NestedMap::default();
+    map.insert(1, 1, &pin());
+    map.insert(2, 2, &pin());
+    assert_eq!(map.len(), 2);
+
+    assert_eq!(map.insert(1, 100, &pin()), Some(1));
+    assert_eq!(map.len(), 2);
+}
+
+#[test]
+fn alter_adjusts_len() {
+    let map: NestedMap<i32, i32> = NestedMap::new();
+
+    map.alter(1, |_| Some(5), &pin());
+    assert_eq!(map.len(), 1);
+
+    // Updating in place keeps the count steady.
+    map.alter(1, |v| v.map(|x| x + 1), &pin());
+    assert_eq!(map.len(), 1);
+
+    map.alter(1, |_| None, &pin());
+    assert_eq!(map.len(), 0);
+
+    // Altering an absent key to `None` is a no-op.
+    map.alter(2, |_| None, &pin());
+    assert_eq!(map.len(), 0);
+}
@@ -0,0 +1,32 @@
+use std::collections::HashSet;
+
+use crossbeam_epoch::pin;
+use nested_map::nested_map::NestedMap;
+use rayon::iter::ParallelIterator;
+
+#[test]
+fn par_collect_equals_seq_collect() {
+    let map = NestedMap::default();
+    for i in 0..10000 {
+        map.insert(i, i * 3, &pin());
+    }
+
+    let guard = pin();
+    let seq: HashSet<_> = map.iter(&guard).map(|(k, v)| (*k, *v)).collect();
+    let par: HashSet<_> = map.par_iter(&guard).map(|(k, v)| (*k, *v)).collect();
+
+    assert_eq!(par.len(), 10000);
+    assert_eq!(seq, par);
+}
+
+#[test]
+fn par_sum_matches_sequential() {
+    let map = NestedMap::default();
+    for i in 0..1000u64 {
+        map.insert(i, i, &pin());
+    }
+
+    let guard = pin();
+    let total: u64 = map.par_iter(&guard).map(|(_, v)| *v).sum();
+    assert_eq!(total, (0..1000u64).sum());
+}
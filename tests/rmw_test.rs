@@ -0,0 +1,99 @@
+use std::hash::{BuildHasher, Hasher};
+
+use crossbeam_epoch::pin;
+use nested_map::digest::HashDigester;
+use nested_map::nested_map::NestedMap;
+use rayon::prelude::*;
+
+#[test]
+fn update_replaces_existing_value() {
+    let map = NestedMap::default();
+    map.insert(1, 10, &pin());
+
+    assert_eq!(map.update(1, |v| v + 5, &pin()), Some(10));
+    assert_eq!(map.lookup(&1, &pin()), Some(&15));
+}
+
+#[test]
+fn update_absent_key_is_noop() {
+    let map: NestedMap<i32, i32> = NestedMap::new();
+    assert_eq!(map.update(1, |v| v + 1, &pin()), None);
+    assert_eq!(map.lookup(&1, &pin()), None);
+}
+
+#[test]
+fn alter_inserts_updates_and_removes() {
+    let map = NestedMap::default();
+
+    map.alter(1, |_| Some(1), &pin());
+    assert_eq!(map.lookup(&1, &pin()), Some(&1));
+
+    map.alter(1, |v| v.map(|x| x + 9), &pin());
+    assert_eq!(map.lookup(&1, &pin()), Some(&10));
+
+    map.alter(1, |_| None, &pin());
+    assert_eq!(map.lookup(&1, &pin()), None);
+}
+
+#[test]
+fn update_under_contention_loses_no_writes() {
+    let map = NestedMap::default();
+    map.insert(0u64, 0u64, &pin());
+
+    let threads = 8u64;
+    let per_thread = 2000u64;
+    (0..threads).into_par_iter().for_each(|_| {
+        for _ in 0..per_thread {
+            map.update(0, |v| v + 1, &pin());
+        }
+    });
+
+    assert_eq!(map.lookup(&0, &pin()), Some(&(threads * per_thread)));
+}
+
+/// A hasher that zeroes the low byte, so every key shares the root radix index
+/// and `alter` must repeatedly split the same contended bucket.
+#[derive(Clone, Default)]
+struct RootCollide;
+
+impl BuildHasher for RootCollide {
+    type Hasher = RootCollideHasher;
+
+    fn build_hasher(&self) -> RootCollideHasher {
+        RootCollideHasher(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+struct RootCollideHasher(u64);
+
+impl Hasher for RootCollideHasher {
+    fn finish(&self) -> u64 {
+        self.0 & !0xFF
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+#[test]
+fn alter_under_contention_places_every_key() {
+    let map = NestedMap::with_hasher(HashDigester::new(RootCollide));
+    let threads = 8u64;
+    let per_thread = 4000u64;
+
+    (0..threads).into_par_iter().for_each(|t| {
+        for i in 0..per_thread {
+            let key = t * per_thread + i;
+            map.alter(key, |_| Some(key), &pin());
+        }
+    });
+
+    assert_eq!(map.len() as u64, threads * per_thread);
+    let guard = pin();
+    for key in 0..(threads * per_thread) {
+        assert_eq!(map.lookup(&key, &guard), Some(&key));
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "serde")]
+
+use crossbeam_epoch::pin;
+use nested_map::nested_map::NestedMap;
+
+#[test]
+fn json_round_trip() {
+    let map: NestedMap<String, i32> = NestedMap::new();
+    map.insert("one".to_string(), 1, &pin());
+    map.insert("two".to_string(), 2, &pin());
+    map.insert("three".to_string(), 3, &pin());
+
+    let json = serde_json::to_string(&map).unwrap();
+    let restored: NestedMap<String, i32> = serde_json::from_str(&json).unwrap();
+
+    let guard = pin();
+    assert_eq!(restored.len(), 3);
+    assert_eq!(restored.lookup(&"one".to_string(), &guard), Some(&1));
+    assert_eq!(restored.lookup(&"two".to_string(), &guard), Some(&2));
+    assert_eq!(restored.lookup(&"three".to_string(), &guard), Some(&3));
+}
+
+#[test]
+fn bincode_round_trip() {
+    // bincode is length-prefixed, so this exercises `serialize_map(Some(len))`.
+    let map: NestedMap<u32, u32> = NestedMap::new();
+    for i in 0..50 {
+        map.insert(i, i * i, &pin());
+    }
+
+    let bytes = bincode::serialize(&map).unwrap();
+    let restored: NestedMap<u32, u32> = bincode::deserialize(&bytes).unwrap();
+
+    let guard = pin();
+    assert_eq!(restored.len(), 50);
+    for i in 0..50 {
+        assert_eq!(restored.lookup(&i, &guard), Some(&(i * i)));
+    }
+}